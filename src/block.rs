@@ -0,0 +1,113 @@
+use sha2::{Digest, Sha256};
+use std::mem;
+
+use crate::merkle;
+use crate::transaction::Txs;
+use crate::util::to_hex;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Block {
+    pub timestamp: u64,
+    pub transactions: Txs,
+    pub prev_hash: String,
+    pub merkle_root: String,
+    pub bits: u32,
+    pub nonce: u64,
+    pub hash: String,
+}
+
+impl Block {
+    // Number of leading zero bits a block hash must have, i.e. the hash
+    // interpreted as a big-endian 256-bit integer must be <= 2^(256-bits).
+    fn target(bits: u32) -> [u8; 32] {
+        if bits == 0 {
+            return [0xff; 32];
+        }
+        let bit_pos = 256 - bits.min(256);
+        let mut target = [0u8; 32];
+        let byte = 31 - (bit_pos / 8) as usize;
+        target[byte] = 1 << (bit_pos % 8);
+        target
+    }
+
+    fn header_digest(timestamp: u64, merkle_root: &str, prev_hash: &str, bits: u32, nonce: u64) -> [u8; 32] {
+        let ts_bytes: [u8; 8] = unsafe { mem::transmute(timestamp.to_be()) };
+        let bits_bytes: [u8; 4] = unsafe { mem::transmute(bits.to_be()) };
+        let nonce_bytes: [u8; 8] = unsafe { mem::transmute(nonce.to_be()) };
+        let mut hash = Sha256::default();
+        hash.input(&ts_bytes);
+        hash.input(merkle_root.as_bytes());
+        hash.input(prev_hash.as_bytes());
+        hash.input(&bits_bytes);
+        hash.input(&nonce_bytes);
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&hash.result());
+        digest
+    }
+
+    fn leaves(txs: &Txs) -> Vec<String> {
+        txs.iter().map(|tx| tx.id.clone()).collect()
+    }
+
+    pub fn mine(txs: Txs, prev_hash: &str, bits: u32) -> Self {
+        let ts = crate::now_millis();
+        let merkle_root = merkle::root(&Self::leaves(&txs));
+        let target = Self::target(bits);
+        let mut nonce = 0u64;
+        loop {
+            let digest = Self::header_digest(ts, &merkle_root, prev_hash, bits, nonce);
+            if digest <= target {
+                return Block {
+                    timestamp: ts,
+                    transactions: txs,
+                    prev_hash: prev_hash.to_owned(),
+                    merkle_root,
+                    bits,
+                    nonce,
+                    hash: to_hex(&digest),
+                };
+            }
+            nonce += 1;
+        }
+    }
+
+    pub fn validate_pow(&self) -> bool {
+        if self.merkle_root != merkle::root(&Self::leaves(&self.transactions)) {
+            return false;
+        }
+        let digest = Self::header_digest(self.timestamp, &self.merkle_root, &self.prev_hash, self.bits, self.nonce);
+        to_hex(&digest) == self.hash && digest <= Self::target(self.bits)
+    }
+
+    // Sibling hashes and left/right flags proving `txid` is included under
+    // this block's merkle root, for a light client that only has the root.
+    pub fn merkle_proof(&self, txid: &str) -> Option<Vec<(String, bool)>> {
+        merkle::proof(&Self::leaves(&self.transactions), txid)
+    }
+
+    pub fn save(&self, db: &rocksdb::DB) {
+        let hash = &self.hash.as_bytes();
+        let encoded: Vec<u8> = bincode::serialize(&self, bincode::Infinite).unwrap();
+        db.put(hash, &encoded).unwrap();
+        db.put(b"tip", hash).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::Transaction;
+
+    #[test]
+    fn mined_block_validates_pow() {
+        let block = Block::mine(vec!(Transaction::coinbase("addr")), "", 8);
+        assert!(block.validate_pow());
+    }
+
+    #[test]
+    fn tampered_block_fails_pow() {
+        let mut block = Block::mine(vec!(Transaction::coinbase("addr")), "", 8);
+        block.nonce = block.nonce.wrapping_add(1);
+        assert!(!block.validate_pow());
+    }
+}
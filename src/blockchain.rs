@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use rocksdb::DB;
+use secp256k1::SecretKey;
+
+use crate::block::Block;
+use crate::crypto;
+use crate::mempool::Mempool;
+use crate::transaction::{Input, Output, Transaction, Txs};
+use crate::utxo;
+use crate::wallet::Wallet;
+
+// Difficulty used when mining new blocks: number of leading zero bits
+// the block hash must have.
+const BITS: u32 = 16;
+
+type IndexMap = HashMap<String, Vec<i8>>;
+
+pub struct Blockchain {
+    db: DB,
+    tip: String,
+}
+
+impl Blockchain {
+    pub fn new() -> Self {
+        Self::open("./data")
+    }
+
+    // Opens (or initializes) a chain at an arbitrary RocksDB path, so tests
+    // can exercise a chain without touching the real `./data` database.
+    pub(crate) fn open(path: &str) -> Self {
+        let db = DB::open_default(path).unwrap();
+        let tip = match db.get(b"tip") {
+            Ok(Some(val)) => String::from_utf8(val.to_vec()).unwrap(),
+            Ok(None) => {
+                // A wallet scoped to this chain's own path, not the shared
+                // `./wallet.keystore`, so opening a chain (including a
+                // throwaway test database) never mutates global wallet state.
+                let reward_addr = Wallet::open(&format!("{}.keystore", path)).new_address();
+                let block = Block::mine(vec!(Transaction::coinbase(&reward_addr)), "", BITS);
+                block.save(&db);
+                utxo::apply(&db, &block.transactions);
+                db.put(b"utxo_indexed", b"1").unwrap();
+                block.hash
+            },
+            Err(err) => panic!(err),
+        };
+        let mut chain = Blockchain { db, tip };
+        if chain.db.get(b"utxo_indexed").unwrap().is_none() {
+            chain.reindex();
+        }
+        chain
+    }
+
+    // Rebuilds the UTXO index from scratch by replaying every block
+    // forward from genesis. Needed once for databases that predate it.
+    fn reindex(&mut self) {
+        utxo::reindex(&self.db, self.blocks());
+        self.db.put(b"utxo_indexed", b"1").unwrap();
+    }
+
+    pub fn blocks(&self) -> Vec<Block> {
+        let mut tip = self.tip.clone();
+        let mut blocks = vec!();
+        while tip != "" {
+            let encoded = self.db.get(&tip.as_bytes()).unwrap().unwrap();
+            let block: Block = bincode::deserialize(&encoded[..]).unwrap();
+            if !block.validate_pow() {
+                panic!("block {} fails proof-of-work", block.hash);
+            }
+            tip = block.prev_hash.clone();
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    pub fn add(&mut self, txs: Txs) -> Block {
+        for tx in &txs {
+            if !tx.is_coinbase() && !tx.verify(&self.prevouts(tx)) {
+                panic!("invalid transaction {}", tx.id);
+            }
+        }
+        let block = Block::mine(txs, &self.tip, BITS);
+        block.save(&self.db);
+        utxo::apply(&self.db, &block.transactions);
+        self.tip = block.hash.clone();
+        block
+    }
+
+    fn find_output(&self, txid: &str, index: i8) -> Output {
+        utxo::get(&self.db, txid, index).unwrap_or_else(|| panic!("unknown output {}:{}", txid, index))
+    }
+
+    fn prevouts(&self, tx: &Transaction) -> HashMap<(String, i8), Output> {
+        let mut prevouts = HashMap::new();
+        for input in &tx.inputs {
+            prevouts.insert((input.txid.clone(), input.index), self.find_output(&input.txid, input.index));
+        }
+        prevouts
+    }
+
+    pub fn utxos(&self, addr: &str) -> Vec<Output> {
+        utxo::utxos(&self.db, addr).into_iter().map(|(_, _, out)| out).collect()
+    }
+
+    pub fn balance(&self, addr: &str) -> u64 {
+        self.utxos(addr).iter().fold(0, |a, b| a + b.amount)
+    }
+
+    fn unspent_outputs(&self, addr: &str, amount: u64) -> (u64, IndexMap) {
+        let mut sum = 0;
+        let mut unspent_outs: IndexMap = HashMap::new();
+        for (txid, index, out) in utxo::utxos(&self.db, addr) {
+            if sum >= amount {
+                break;
+            }
+            sum += out.amount;
+            unspent_outs.entry(txid).or_insert(vec!()).push(index);
+        }
+        (sum, unspent_outs)
+    }
+
+    pub fn send(&self, from: &SecretKey, to: &str, amount: u64) -> Transaction {
+        let from_pubkey = crypto::pubkey_hex(from);
+        let from_addr = crypto::address(from);
+        let (sum, unspent_outs) = self.unspent_outputs(&from_addr, amount);
+        if sum < amount {
+            panic!("insufficient funds");
+        }
+        let mut outputs = vec!();
+        outputs.push(Output { amount, pubkey: to.to_owned() });
+        if sum > amount {
+            outputs.push(Output { amount: sum - amount, pubkey: from_addr.clone() });
+        }
+        let mut inputs = vec!();
+        for (txid, outs) in unspent_outs {
+            for index in outs {
+                let prevout = self.find_output(&txid, index);
+                let msg = Transaction::sign_message(&txid, index, &prevout, &outputs);
+                let sig = crypto::sign(from, &msg);
+                inputs.push(Input { txid: txid.clone(), index, pubkey: from_pubkey.clone(), sig });
+            }
+        }
+        Transaction::new(inputs, outputs)
+    }
+
+    pub fn submit(&self, tx: Transaction) -> Result<(), String> {
+        Mempool::new(&self.db).submit(tx)
+    }
+
+    // Drains the mempool into a single new block, crediting `coinbase_addr`
+    // with the block reward.
+    pub fn mine(&mut self, coinbase_addr: &str) -> Block {
+        let mempool = Mempool::new(&self.db);
+        let mut txs = mempool.drain();
+        // The coinbase input carries the tip this block builds on, so two
+        // rewards to the same address get distinct ids (and utxo keys).
+        let mut coinbase = Transaction::coinbase(coinbase_addr);
+        coinbase.inputs[0].sig = self.tip.clone();
+        txs.insert(0, Transaction::new(coinbase.inputs, coinbase.outputs));
+        self.add(txs)
+    }
+
+    // How many blocks deep a transaction is from the tip; 0 if it's still
+    // only in the mempool (or unknown).
+    pub fn confirmations(&self, txid: &str) -> u64 {
+        if Mempool::new(&self.db).contains(txid) {
+            return 0;
+        }
+        for (depth, block) in self.blocks().into_iter().enumerate() {
+            if block.transactions.iter().any(|tx| tx.id == txid) {
+                return (depth + 1) as u64;
+            }
+        }
+        0
+    }
+
+    pub fn find_tx(&self, txid: &str) -> Option<Transaction> {
+        if let Some(tx) = Mempool::new(&self.db).transactions().into_iter().find(|tx| tx.id == txid) {
+            return Some(tx);
+        }
+        for block in self.blocks() {
+            if let Some(tx) = block.transactions.into_iter().find(|tx| tx.id == txid) {
+                return Some(tx);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    // Each test opens its own throwaway DB so the suite doesn't touch the
+    // real `./data` directory or interfere with other tests.
+    fn fresh_chain(name: &str) -> Blockchain {
+        let path = format!("./data-test-{}", name);
+        let _ = fs::remove_dir_all(&path);
+        Blockchain::open(&path)
+    }
+
+    #[test]
+    fn balance_reflects_send_and_mine() {
+        let mut chain = fresh_chain("balance_reflects_send_and_mine");
+        let mut wallet = Wallet::open("./wallet-test-balance_reflects_send_and_mine.keystore");
+        let alice = wallet.new_address();
+        let bob = wallet.new_address();
+
+        chain.mine(&alice);
+        assert_eq!(chain.balance(&alice), 100);
+
+        let seckey = wallet.secret_key(&alice).unwrap().clone();
+        let tx = chain.send(&seckey, &bob, 10);
+        chain.submit(tx).unwrap();
+        chain.mine(&alice);
+
+        assert_eq!(chain.balance(&bob), 10);
+        assert_eq!(chain.balance(&alice), 190);
+    }
+}
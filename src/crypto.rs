@@ -0,0 +1,71 @@
+use ripemd160::Ripemd160;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey, Signature};
+use sha2::{Digest, Sha256};
+
+use crate::util::{base58_encode, from_hex, to_hex};
+
+// Address version byte, as in rust-bitcoin's mainnet P2PKH prefix.
+const ADDR_VERSION: u8 = 0x00;
+
+fn digest(msg: &[u8]) -> [u8; 32] {
+    let mut hash = Sha256::default();
+    hash.input(msg);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hash.result());
+    out
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    let mut ripemd = Ripemd160::default();
+    ripemd.input(&digest(data));
+    let mut out = [0u8; 20];
+    out.copy_from_slice(&ripemd.result());
+    out
+}
+
+pub fn pubkey_hex(seckey: &SecretKey) -> String {
+    let secp = Secp256k1::new();
+    to_hex(&PublicKey::from_secret_key(&secp, seckey).serialize())
+}
+
+// Base58Check address: version_byte || RIPEMD160(SHA256(pubkey)) || 4-byte
+// checksum (the first 4 bytes of the double-SHA256 of the payload).
+pub fn address_from_pubkey(pubkey: &[u8]) -> String {
+    let mut payload = vec!(ADDR_VERSION);
+    payload.extend_from_slice(&hash160(pubkey));
+    let checksum = digest(&digest(&payload));
+    payload.extend_from_slice(&checksum[..4]);
+    base58_encode(&payload)
+}
+
+pub fn address_from_pubkey_hex(pubkey_hex: &str) -> String {
+    address_from_pubkey(&from_hex(pubkey_hex))
+}
+
+pub fn address(seckey: &SecretKey) -> String {
+    let secp = Secp256k1::new();
+    address_from_pubkey(&PublicKey::from_secret_key(&secp, seckey).serialize())
+}
+
+pub fn decode_pubkey(hex: &str) -> Option<PublicKey> {
+    PublicKey::from_slice(&from_hex(hex)).ok()
+}
+
+pub fn sign(seckey: &SecretKey, msg: &[u8]) -> String {
+    let secp = Secp256k1::new();
+    let message = Message::from_slice(&digest(msg)).unwrap();
+    to_hex(&secp.sign(&message, seckey).serialize_compact())
+}
+
+pub fn verify(pubkey: &PublicKey, msg: &[u8], sig_hex: &str) -> bool {
+    let secp = Secp256k1::new();
+    let message = match Message::from_slice(&digest(msg)) {
+        Ok(m) => m,
+        Err(_) => return false,
+    };
+    let sig = match Signature::from_compact(&from_hex(sig_hex)) {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+    secp.verify(&message, &sig, pubkey).is_ok()
+}
@@ -0,0 +1,90 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+
+use crate::block::Block;
+use crate::blockchain::Blockchain;
+use crate::transaction::{Output, Transaction};
+
+type Shared = Arc<Mutex<Blockchain>>;
+
+#[derive(Serialize)]
+struct BlockSummary {
+    hash: String,
+    timestamp: u64,
+    prev_hash: String,
+    tx_count: usize,
+}
+
+impl From<Block> for BlockSummary {
+    fn from(block: Block) -> Self {
+        BlockSummary {
+            hash: block.hash,
+            timestamp: block.timestamp,
+            prev_hash: block.prev_hash,
+            tx_count: block.transactions.len(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Page {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+async fn list_blocks(State(chain): State<Shared>, Query(page): Query<Page>) -> Json<Vec<BlockSummary>> {
+    let chain = chain.lock().unwrap();
+    let offset = page.offset.unwrap_or(0);
+    let limit = page.limit.unwrap_or(20);
+    let summaries = chain.blocks().into_iter().skip(offset).take(limit).map(BlockSummary::from).collect();
+    Json(summaries)
+}
+
+async fn get_block(State(chain): State<Shared>, Path(hash): Path<String>) -> Result<Json<Block>, StatusCode> {
+    let chain = chain.lock().unwrap();
+    chain.blocks().into_iter().find(|b| b.hash == hash).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_tx(State(chain): State<Shared>, Path(id): Path<String>) -> Result<Json<Transaction>, StatusCode> {
+    let chain = chain.lock().unwrap();
+    chain.find_tx(&id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+#[derive(Serialize)]
+struct AddressInfo {
+    balance: u64,
+    utxos: Vec<Output>,
+}
+
+async fn get_address(State(chain): State<Shared>, Path(addr): Path<String>) -> Json<AddressInfo> {
+    let chain = chain.lock().unwrap();
+    Json(AddressInfo { balance: chain.balance(&addr), utxos: chain.utxos(&addr) })
+}
+
+async fn post_tx(State(chain): State<Shared>, Json(tx): Json<Transaction>) -> Result<StatusCode, (StatusCode, String)> {
+    let chain = chain.lock().unwrap();
+    chain.submit(tx).map(|_| StatusCode::ACCEPTED).map_err(|err| (StatusCode::BAD_REQUEST, err))
+}
+
+fn router(chain: Shared) -> Router {
+    Router::new()
+        .route("/blocks", get(list_blocks))
+        .route("/block/:hash", get(get_block))
+        .route("/tx/:id", get(get_tx))
+        .route("/address/:addr", get(get_address))
+        .route("/tx", post(post_tx))
+        .with_state(chain)
+}
+
+pub fn serve(chain: Blockchain, port: u16) {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let shared: Shared = Arc::new(Mutex::new(chain));
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        axum::Server::bind(&addr).serve(router(shared).into_make_service()).await.unwrap();
+    });
+}
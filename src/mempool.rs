@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use rocksdb::DB;
+
+use crate::transaction::Transaction;
+use crate::utxo;
+
+// Validated-but-unconfirmed transactions, persisted under their own key
+// prefix so the pool survives restarts.
+const PREFIX: &str = "mempool:";
+
+fn key(txid: &str) -> String {
+    format!("{}{}", PREFIX, txid)
+}
+
+pub struct Mempool<'a> {
+    db: &'a DB,
+}
+
+impl<'a> Mempool<'a> {
+    pub fn new(db: &'a DB) -> Self {
+        Mempool { db }
+    }
+
+    pub fn submit(&self, tx: Transaction) -> Result<(), String> {
+        if tx.is_coinbase() {
+            return Err("coinbase transactions can't be submitted to the mempool".to_owned());
+        }
+        let mut prevouts = HashMap::new();
+        for input in &tx.inputs {
+            match utxo::get(self.db, &input.txid, input.index) {
+                Some(out) => {
+                    prevouts.insert((input.txid.clone(), input.index), out);
+                },
+                None => return Err(format!("unknown or already-spent output {}:{}", input.txid, input.index)),
+            }
+        }
+        if !tx.verify(&prevouts) {
+            return Err(format!("invalid transaction {}", tx.id));
+        }
+        for pending in self.transactions() {
+            for input in &tx.inputs {
+                if pending.inputs.iter().any(|i| i.txid == input.txid && i.index == input.index) {
+                    return Err(format!("double spend of {}:{}", input.txid, input.index));
+                }
+            }
+        }
+        self.db.put(key(&tx.id).as_bytes(), &bincode::serialize(&tx, bincode::Infinite).unwrap()).unwrap();
+        Ok(())
+    }
+
+    pub fn transactions(&self) -> Vec<Transaction> {
+        let mut txs = vec!();
+        for (k, v) in self.db.prefix_iterator(PREFIX.as_bytes()) {
+            if !k.starts_with(PREFIX.as_bytes()) {
+                break;
+            }
+            txs.push(bincode::deserialize(&v[..]).unwrap());
+        }
+        txs
+    }
+
+    pub fn contains(&self, txid: &str) -> bool {
+        self.transactions().iter().any(|tx| tx.id == txid)
+    }
+
+    // Drains the whole pool, used when a new block is mined from it.
+    pub fn drain(&self) -> Vec<Transaction> {
+        let txs = self.transactions();
+        for tx in &txs {
+            self.db.delete(key(&tx.id).as_bytes()).unwrap();
+        }
+        txs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use secp256k1::Secp256k1;
+
+    use super::*;
+    use crate::crypto;
+    use crate::transaction::{Input, Output};
+
+    fn fresh_db(name: &str) -> DB {
+        let path = format!("./data-test-mempool-{}", name);
+        let _ = fs::remove_dir_all(&path);
+        DB::open_default(&path).unwrap()
+    }
+
+    #[test]
+    fn double_spend_is_rejected() {
+        let db = fresh_db("double_spend_is_rejected");
+        let secp = Secp256k1::new();
+        let (seckey, _) = secp.generate_keypair(&mut rand::thread_rng());
+        let addr = crypto::address(&seckey);
+
+        let coinbase = Transaction::coinbase(&addr);
+        let txid = coinbase.id.clone();
+        let prevout = coinbase.outputs[0].clone();
+        utxo::apply(&db, &vec!(coinbase));
+
+        let outputs = vec!(Output { amount: 10, pubkey: "elsewhere".to_owned() });
+        let msg = Transaction::sign_message(&txid, 0, &prevout, &outputs);
+        let sig = crypto::sign(&seckey, &msg);
+        let input = Input { txid: txid.clone(), index: 0, pubkey: crypto::pubkey_hex(&seckey), sig };
+
+        let mempool = Mempool::new(&db);
+        let first = Transaction::new(vec!(input.clone()), outputs.clone());
+        assert!(mempool.submit(first).is_ok());
+
+        let second = Transaction::new(vec!(input), outputs);
+        assert!(mempool.submit(second).is_err());
+    }
+
+    #[test]
+    fn inflated_outputs_are_rejected() {
+        let db = fresh_db("inflated_outputs_are_rejected");
+        let secp = Secp256k1::new();
+        let (seckey, _) = secp.generate_keypair(&mut rand::thread_rng());
+        let addr = crypto::address(&seckey);
+
+        // A single coin-sized prevout...
+        let funding = Transaction::coinbase(&addr);
+        let txid = funding.id.clone();
+        let mut prevout = funding.outputs[0].clone();
+        prevout.amount = 1;
+        utxo::apply(&db, &vec!(Transaction { outputs: vec!(prevout.clone()), ..funding }));
+
+        // ...signed over outputs worth far more than it covers.
+        let outputs = vec!(Output { amount: 100, pubkey: "elsewhere".to_owned() });
+        let msg = Transaction::sign_message(&txid, 0, &prevout, &outputs);
+        let sig = crypto::sign(&seckey, &msg);
+        let input = Input { txid, index: 0, pubkey: crypto::pubkey_hex(&seckey), sig };
+
+        let tx = Transaction::new(vec!(input), outputs);
+        assert!(Mempool::new(&db).submit(tx).is_err());
+    }
+}
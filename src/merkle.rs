@@ -0,0 +1,67 @@
+use sha2::{Digest, Sha256};
+
+use crate::util::{from_hex, to_hex};
+
+fn decode(hex: &str) -> [u8; 32] {
+    let bytes = from_hex(hex);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    out
+}
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut hash = Sha256::default();
+    hash.input(a);
+    hash.input(b);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hash.result());
+    out
+}
+
+// Builds the next level of a merkle tree, duplicating the last hash when
+// the current level has an odd number of nodes.
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    let mut level = level.to_vec();
+    if level.len() % 2 == 1 {
+        level.push(*level.last().unwrap());
+    }
+    level.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect()
+}
+
+pub fn root(leaves: &[String]) -> String {
+    if leaves.is_empty() {
+        return to_hex(&[0u8; 32]);
+    }
+    let mut level: Vec<[u8; 32]> = leaves.iter().map(|h| decode(h)).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    to_hex(&level[0])
+}
+
+// Sibling hashes and left/right flags (true if the sibling sits to the
+// left of the node being proven) on the path from `txid` up to the root.
+pub fn proof(leaves: &[String], txid: &str) -> Option<Vec<(String, bool)>> {
+    let mut index = leaves.iter().position(|id| id == txid)?;
+    let mut level: Vec<[u8; 32]> = leaves.iter().map(|h| decode(h)).collect();
+    let mut path = vec!();
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(*level.last().unwrap());
+        }
+        let (sibling_index, sibling_is_left) = if index % 2 == 0 { (index + 1, false) } else { (index - 1, true) };
+        path.push((to_hex(&level[sibling_index]), sibling_is_left));
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(path)
+}
+
+pub fn verify_proof(txid: &str, proof: &[(String, bool)], root: &str) -> bool {
+    let mut current = decode(txid);
+    for (sibling_hex, sibling_is_left) in proof {
+        let sibling = decode(sibling_hex);
+        current = if *sibling_is_left { hash_pair(&sibling, &current) } else { hash_pair(&current, &sibling) };
+    }
+    to_hex(&current) == root
+}
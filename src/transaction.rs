@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::crypto;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Input {
+    pub txid: String,
+    pub index: i8,
+    // compressed secp256k1 public key of the signer, hex-encoded
+    pub pubkey: String,
+    // compact secp256k1 signature over the spend message, hex-encoded
+    pub sig: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Output {
+    pub amount: u64,
+    pub pubkey: String,
+}
+
+impl Output {
+    pub fn unlocked_by(&self, addr: &str) -> bool {
+        self.pubkey == addr
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub id: String,
+    pub inputs: Vec<Input>,
+    pub outputs: Vec<Output>,
+}
+
+impl Transaction {
+    pub fn new(inputs: Vec<Input>, outputs: Vec<Output>) -> Self {
+        let mut tx = Transaction { id: "".to_owned(), inputs, outputs };
+        tx.id = tx.hash();
+        tx
+    }
+
+    pub fn coinbase(reward_addr: &str) -> Self {
+        Transaction::new(
+            vec!(Input { txid: "".to_owned(), index: -1, pubkey: "".to_owned(), sig: "".to_owned() }),
+            vec!(Output { amount: 100, pubkey: reward_addr.to_owned() }),
+        )
+    }
+
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.len() == 1 && {
+            let input = &self.inputs[0];
+            input.txid == "" && input.index == -1
+        }
+    }
+
+    // The message signed for a given input: the outpoint being spent (so a
+    // signature can't be replayed onto another input with an identical
+    // prevout), the referenced output itself, and the transaction's new
+    // outputs. Each input signs its own prevout, since a tx can spend
+    // outputs owned by different keys.
+    pub fn sign_message(txid: &str, index: i8, prevout: &Output, outputs: &[Output]) -> Vec<u8> {
+        let mut bytes = bincode::serialize(&(txid, index), bincode::Infinite).unwrap();
+        bytes.extend(bincode::serialize(prevout, bincode::Infinite).unwrap());
+        for out in outputs {
+            bytes.extend(bincode::serialize(out, bincode::Infinite).unwrap());
+        }
+        bytes
+    }
+
+    // Verifies every input's signature against the outputs it claims to
+    // spend, and that the referenced outputs cover the new ones (otherwise
+    // a validly-signed input over a small prevout could fund inflated
+    // outputs and mint coins). `prevouts` maps (txid, index) to the
+    // referenced Output, which the caller is responsible for looking up
+    // from the chain.
+    pub fn verify(&self, prevouts: &HashMap<(String, i8), Output>) -> bool {
+        if self.is_coinbase() {
+            return true;
+        }
+        let mut prevout_sum = 0u64;
+        for input in &self.inputs {
+            let prevout = match prevouts.get(&(input.txid.clone(), input.index)) {
+                Some(out) => out,
+                None => return false,
+            };
+            if !prevout.unlocked_by(&crypto::address_from_pubkey_hex(&input.pubkey)) {
+                return false;
+            }
+            let pubkey = match crypto::decode_pubkey(&input.pubkey) {
+                Some(pk) => pk,
+                None => return false,
+            };
+            let msg = Transaction::sign_message(&input.txid, input.index, prevout, &self.outputs);
+            if !crypto::verify(&pubkey, &msg, &input.sig) {
+                return false;
+            }
+            prevout_sum += prevout.amount;
+        }
+        let output_sum: u64 = self.outputs.iter().map(|out| out.amount).sum();
+        prevout_sum >= output_sum
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(&self, bincode::Infinite).unwrap()
+    }
+
+    fn hash(&self) -> String {
+        let mut hash = Sha256::default();
+        hash.input(&self.encode());
+        format!("{:x}", hash.result())
+    }
+}
+
+pub type Txs = Vec<Transaction>;
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::Secp256k1;
+
+    use super::*;
+
+    fn keypair() -> secp256k1::SecretKey {
+        let secp = Secp256k1::new();
+        let (seckey, _) = secp.generate_keypair(&mut rand::thread_rng());
+        seckey
+    }
+
+    #[test]
+    fn forged_signature_is_rejected() {
+        let owner = keypair();
+        let forger = keypair();
+        let owner_addr = crypto::address(&owner);
+
+        let prevout = Output { amount: 10, pubkey: owner_addr };
+        let outputs = vec!(Output { amount: 10, pubkey: "somewhere".to_owned() });
+        let msg = Transaction::sign_message("prevtx", 0, &prevout, &outputs);
+        let forged_sig = crypto::sign(&forger, &msg);
+
+        let input = Input { txid: "prevtx".to_owned(), index: 0, pubkey: crypto::pubkey_hex(&owner), sig: forged_sig };
+        let tx = Transaction::new(vec!(input), outputs);
+
+        let mut prevouts = HashMap::new();
+        prevouts.insert(("prevtx".to_owned(), 0), prevout);
+        assert!(!tx.verify(&prevouts));
+    }
+}
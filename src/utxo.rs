@@ -0,0 +1,75 @@
+use rocksdb::DB;
+
+use crate::block::Block;
+use crate::transaction::{Output, Txs};
+
+// Unspent outputs are indexed under utxo:<txid>:<index> -> encoded Output,
+// with a secondary addr:<address>:<txid>:<index> -> utxo key index so
+// per-address lookups don't require scanning every utxo entry.
+fn utxo_key(txid: &str, index: i8) -> String {
+    format!("utxo:{}:{}", txid, index)
+}
+
+fn addr_key(addr: &str, txid: &str, index: i8) -> String {
+    format!("addr:{}:{}:{}", addr, txid, index)
+}
+
+pub fn get(db: &DB, txid: &str, index: i8) -> Option<Output> {
+    match db.get(utxo_key(txid, index).as_bytes()) {
+        Ok(Some(encoded)) => Some(bincode::deserialize(&encoded[..]).unwrap()),
+        _ => None,
+    }
+}
+
+fn insert(db: &DB, txid: &str, index: i8, out: &Output) {
+    let key = utxo_key(txid, index);
+    db.put(key.as_bytes(), &bincode::serialize(out, bincode::Infinite).unwrap()).unwrap();
+    db.put(addr_key(&out.pubkey, txid, index).as_bytes(), key.as_bytes()).unwrap();
+}
+
+fn remove(db: &DB, txid: &str, index: i8) {
+    if let Some(out) = get(db, txid, index) {
+        db.delete(addr_key(&out.pubkey, txid, index).as_bytes()).unwrap();
+    }
+    db.delete(utxo_key(txid, index).as_bytes()).unwrap();
+}
+
+// Applied incrementally as each block is added: consumed inputs drop out
+// of the index, new outputs join it.
+pub fn apply(db: &DB, txs: &Txs) {
+    for tx in txs {
+        if !tx.is_coinbase() {
+            for input in &tx.inputs {
+                remove(db, &input.txid, input.index);
+            }
+        }
+        for (i, out) in tx.outputs.iter().enumerate() {
+            insert(db, &tx.id, i as i8, out);
+        }
+    }
+}
+
+pub fn utxos(db: &DB, addr: &str) -> Vec<(String, i8, Output)> {
+    let prefix = format!("addr:{}:", addr);
+    let mut utxos = vec!();
+    for (key, utxo_key) in db.prefix_iterator(prefix.as_bytes()) {
+        let key = String::from_utf8(key.to_vec()).unwrap();
+        if !key.starts_with(&prefix) {
+            break;
+        }
+        let rest: Vec<&str> = key[prefix.len()..].splitn(2, ':').collect();
+        let (txid, index) = (rest[0].to_owned(), rest[1].parse().unwrap());
+        if let Ok(Some(encoded)) = db.get(&utxo_key) {
+            utxos.push((txid, index, bincode::deserialize(&encoded[..]).unwrap()));
+        }
+    }
+    utxos
+}
+
+// One-time rebuild for databases that predate the utxo index: replays
+// every block forward from genesis instead of on every balance query.
+pub fn reindex(db: &DB, blocks: Vec<Block>) {
+    for block in blocks.into_iter().rev() {
+        apply(db, &block.transactions);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fs;
+
+use secp256k1::SecretKey;
+use sha2::{Digest, Sha256};
+
+use crate::crypto;
+use crate::util::{from_hex, to_hex};
+
+const KEYSTORE_PATH: &str = "./wallet.keystore";
+
+// Keyed by the Base58Check address derived from each key, so lookups by
+// address (e.g. for `send`) don't need to re-derive every key on read.
+pub struct Wallet {
+    path: String,
+    keys: HashMap<String, SecretKey>,
+}
+
+impl Wallet {
+    pub fn load() -> Self {
+        Self::open(KEYSTORE_PATH)
+    }
+
+    // Opens a keystore at an arbitrary path, so tests can exercise a wallet
+    // without touching the real `./wallet.keystore`.
+    pub(crate) fn open(path: &str) -> Self {
+        let keys = match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let seckey = SecretKey::from_slice(&from_hex(line)).unwrap();
+                    (crypto::address(&seckey), seckey)
+                })
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        Wallet { path: path.to_owned(), keys }
+    }
+
+    fn save(&self) {
+        let contents: String = self.keys.values().map(|k| format!("{}\n", to_hex(&k[..]))).collect();
+        fs::write(&self.path, contents).unwrap();
+    }
+
+    pub fn new_address(&mut self) -> String {
+        self.insert(random_secret_key())
+    }
+
+    // Deterministic child keys derived from a single seed, so many
+    // addresses can share one backup instead of needing per-key backups.
+    pub fn new_child_address(&mut self, seed: &[u8], index: u32) -> String {
+        self.insert(derive_child(seed, index))
+    }
+
+    fn insert(&mut self, seckey: SecretKey) -> String {
+        let addr = crypto::address(&seckey);
+        self.keys.insert(addr.clone(), seckey);
+        self.save();
+        addr
+    }
+
+    pub fn addresses(&self) -> Vec<String> {
+        self.keys.keys().cloned().collect()
+    }
+
+    pub fn secret_key(&self, addr: &str) -> Option<&SecretKey> {
+        self.keys.get(addr)
+    }
+}
+
+fn random_secret_key() -> SecretKey {
+    let secp = secp256k1::Secp256k1::new();
+    let (seckey, _) = secp.generate_keypair(&mut rand::thread_rng());
+    seckey
+}
+
+fn derive_child(seed: &[u8], index: u32) -> SecretKey {
+    let index_bytes: [u8; 4] = unsafe { std::mem::transmute(index.to_be()) };
+    let mut hash = Sha256::default();
+    hash.input(seed);
+    hash.input(&index_bytes);
+    SecretKey::from_slice(&hash.result()).unwrap()
+}